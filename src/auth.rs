@@ -0,0 +1,74 @@
+//! Bearer-token auth middleware protecting the billable endpoints (`/text`, `/image`),
+//! which each trigger a paid OpenAI and/or APIFlash call, and `/image/last`, which serves
+//! the last screenshot one of them paid for.
+//!
+//! If `LLM_JWT_SECRET` is set, only a JWT signed with it and carrying an expiry claim,
+//! verified via `jsonwebtoken`, is accepted. Otherwise the token must equal the shared
+//! secret `LLM_API_SECRET`.
+
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{DecodingKey, Validation};
+use serde::Deserialize;
+use std::env;
+
+#[derive(Deserialize)]
+struct Claims {
+    exp: usize,
+}
+
+/// Rejects the request with `401` unless its `Authorization: Bearer <token>` header
+/// validates against the configured secret.
+pub async fn require_bearer_token(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(strip_bearer_prefix)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if !is_valid_token(token) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Strips the `Bearer` auth scheme, matched case-insensitively per RFC 6750/7235, leaving
+/// just the token.
+fn strip_bearer_prefix(header: &str) -> Option<&str> {
+    let (scheme, token) = header.split_once(' ')?;
+    scheme.eq_ignore_ascii_case("Bearer").then_some(token)
+}
+
+fn is_valid_token(token: &str) -> bool {
+    if let Ok(jwt_secret) = env::var("LLM_JWT_SECRET") {
+        return jsonwebtoken::decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(jwt_secret.as_bytes()),
+            &Validation::default(),
+        )
+        .is_ok();
+    }
+
+    match env::var("LLM_API_SECRET") {
+        Ok(secret) => constant_time_eq(token.as_bytes(), secret.as_bytes()),
+        Err(_) => {
+            log::error!("Neither LLM_API_SECRET nor LLM_JWT_SECRET is set, rejecting all requests");
+            false
+        }
+    }
+}
+
+/// Compares two byte slices in constant time, so a mismatching shared secret can't be
+/// brute-forced via response timing.
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,114 @@
+//! A small in-memory cache for generated ICS content (and, for URLs, the screenshot that
+//! produced it), keyed by a hash of the normalized input. Avoids spending OpenAI/APIFlash
+//! calls again on an input we've already seen.
+
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    time::{Duration, Instant},
+};
+
+use log::debug;
+use tokio::sync::RwLock;
+
+/// What kind of input produced a cache entry. Used only for diagnostics.
+#[derive(Debug, Clone, Copy)]
+pub enum ContentKind {
+    Text,
+    Url,
+    Image,
+}
+
+#[derive(Debug, Clone)]
+struct Metadata {
+    created_at: Instant,
+    content_kind: ContentKind,
+    size_bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    ics: String,
+    screenshot: Option<Vec<u8>>,
+    metadata: Metadata,
+}
+
+/// Hashes any normalized input (trimmed text, a URL's string form, or image bytes) into a
+/// stable cache key.
+pub fn key(input: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cache of generated calendars, bounded by age (`ttl`) and entry count (`max_entries`).
+pub struct Cache {
+    entries: RwLock<HashMap<u64, Entry>>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl Cache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// Returns the cached ICS (and screenshot, if any) for `key`, unless it's missing or
+    /// has outlived the TTL.
+    pub async fn get(&self, key: u64) -> Option<(String, Option<Vec<u8>>)> {
+        let entries = self.entries.read().await;
+        let entry = entries.get(&key)?;
+
+        if entry.metadata.created_at.elapsed() > self.ttl {
+            return None;
+        }
+
+        Some((entry.ics.clone(), entry.screenshot.clone()))
+    }
+
+    /// Stores `ics` (and an optional `screenshot`) under `key`, evicting the oldest entry
+    /// first if the cache is full.
+    pub async fn put(
+        &self,
+        key: u64,
+        ics: String,
+        screenshot: Option<Vec<u8>>,
+        content_kind: ContentKind,
+    ) {
+        let mut entries = self.entries.write().await;
+
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            if let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.metadata.created_at)
+                .map(|(key, _)| *key)
+            {
+                if let Some(evicted) = entries.remove(&oldest) {
+                    debug!(
+                        "Cache full, evicted a {:?} entry ({} bytes) to make room",
+                        evicted.metadata.content_kind, evicted.metadata.size_bytes
+                    );
+                }
+            }
+        }
+
+        let size_bytes = ics.len() + screenshot.as_ref().map_or(0, Vec::len);
+        debug!("Caching a {content_kind:?} entry ({size_bytes} bytes)");
+        entries.insert(
+            key,
+            Entry {
+                ics,
+                screenshot,
+                metadata: Metadata {
+                    created_at: Instant::now(),
+                    content_kind,
+                    size_bytes,
+                },
+            },
+        );
+    }
+}
@@ -1,10 +1,12 @@
 use axum::{
-    extract::{DefaultBodyLimit, Multipart, State},
+    extract::{DefaultBodyLimit, Multipart, Query, State},
     response::{Html, IntoResponse},
     routing::{get, post},
     Form, Router,
 };
 use base64::prelude::*;
+use chrono::{Duration, TimeZone};
+use icalendar::{Calendar, CalendarComponent, Component, Event, EventLike};
 use log::{debug, error, info};
 use openai_api_rs::v1::{
     api::OpenAIClient,
@@ -12,13 +14,45 @@ use openai_api_rs::v1::{
     common::GPT4_O,
 };
 use serde::Deserialize;
-use std::{env, error::Error, fmt::Display, sync::Arc};
+use std::{
+    collections::hash_map::DefaultHasher,
+    env,
+    error::Error,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 use tokio::sync::RwLock;
 use url::Url;
 
+mod auth;
+mod cache;
+
 #[derive(Deserialize, Debug)]
 struct TextRequest {
     text: String,
+    /// Which strategy to use to turn a URL into LLM input. Defaults to
+    /// `URL_EXTRACTION_MODE` env var, or [`UrlExtractionMode::Screenshot`] if unset.
+    mode: Option<UrlExtractionMode>,
+}
+
+/// How a submitted URL is turned into LLM input.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum UrlExtractionMode {
+    /// Fetch the page's HTML and extract readable text from it. Cheaper and faster, but
+    /// doesn't work for JS-rendered pages or non-HTML documents.
+    Html,
+    /// Take an APIFlash screenshot and feed it to the vision model. Slower and costs an
+    /// extra API call, but works for anything a browser can render.
+    Screenshot,
+}
+
+fn default_url_extraction_mode() -> UrlExtractionMode {
+    match env::var("URL_EXTRACTION_MODE").as_deref() {
+        Ok("html") => UrlExtractionMode::Html,
+        _ => UrlExtractionMode::Screenshot,
+    }
 }
 
 type WhateverError = Box<dyn Error + Send + Sync>;
@@ -43,32 +77,45 @@ async fn handle_text(
     debug!("Handling text input: {payload:?}");
 
     let text = payload.text.trim();
+    let mode = payload.mode.unwrap_or_else(default_url_extraction_mode);
 
     match Url::parse(text) {
         Ok(url) => {
-            debug!("Text input is URL: {url}");
+            debug!("Text input is URL: {url}, extraction mode: {mode:?}");
 
-            match process_url(&url, state).await {
-                Ok(ics) => ics.into_response(),
+            match process_url(&url, state.clone(), mode).await {
+                Ok(ics) => {
+                    record_events(&state, &ics).await;
+                    Ics(ics).into_response()
+                }
                 Err(e) => internal_error(e).into_response(),
             }
         }
         Err(_) => {
             debug!("Text input is raw text.");
 
-            match process_text(text).await {
-                Ok(ics) => ics.into_response(),
+            match process_text(text, &state).await {
+                Ok(ics) => {
+                    record_events(&state, &ics).await;
+                    Ics(ics).into_response()
+                }
                 Err(e) => internal_error(e).into_response(),
             }
         }
     }
 }
 
-async fn handle_image(mut multipart: Multipart) -> impl IntoResponse {
+async fn handle_image(
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
     match multipart.next_field().await {
         Ok(Some(field)) => match field.bytes().await {
-            Ok(img) => match fetch_llm_hallucinations(LlmInput::Image(&img)).await {
-                Ok(ics) => ics.into_response(),
+            Ok(img) => match process_image(&img, state.clone()).await {
+                Ok(ics) => {
+                    record_events(&state, &ics).await;
+                    Ics(ics).into_response()
+                }
                 Err(e) => internal_error(e).into_response(),
             },
             Err(e) => {
@@ -99,17 +146,209 @@ async fn handle_image(mut multipart: Multipart) -> impl IntoResponse {
     }
 }
 
-async fn process_text(text: &str) -> Result<String, WhateverError> {
-    fetch_llm_hallucinations(LlmInput::Text(text)).await
+async fn process_text(text: &str, state: &Arc<AppState>) -> Result<String, WhateverError> {
+    let key = cache::key(&text.trim());
+    if let Some((ics, _)) = state.cache.get(key).await {
+        debug!("Cache hit for text input");
+        return Ok(ics);
+    }
+
+    let ics = fetch_llm_hallucinations(LlmInput::Text(text)).await?;
+    state.cache.put(key, ics.clone(), None, cache::ContentKind::Text).await;
+    Ok(ics)
+}
+
+async fn process_image(img: &[u8], state: Arc<AppState>) -> Result<String, WhateverError> {
+    let key = cache::key(&img);
+    if let Some((ics, _)) = state.cache.get(key).await {
+        debug!("Cache hit for image input");
+        return Ok(ics);
+    }
+
+    let ics = fetch_llm_hallucinations(LlmInput::Image(img)).await?;
+    state.cache.put(key, ics.clone(), None, cache::ContentKind::Image).await;
+    Ok(ics)
 }
 
-async fn process_url(url: &Url, state: Arc<AppState>) -> Result<String, WhateverError> {
+async fn process_url(
+    url: &Url,
+    state: Arc<AppState>,
+    mode: UrlExtractionMode,
+) -> Result<String, WhateverError> {
+    let key = cache::key(&url.as_str());
+    if let Some((ics, screenshot)) = state.cache.get(key).await {
+        debug!("Cache hit for URL: {url}");
+        if let Some(screenshot) = screenshot {
+            *state.last_image.write().await = Some(screenshot);
+        }
+        return Ok(ics);
+    }
+
+    if mode == UrlExtractionMode::Html {
+        match fetch_html_text(url).await {
+            Ok(Some(text)) => {
+                let ics = process_text(&text, &state).await?;
+                state
+                    .cache
+                    .put(key, ics.clone(), None, cache::ContentKind::Url)
+                    .await;
+                return Ok(ics);
+            }
+            Ok(None) => {
+                debug!("No usable HTML text for {url}, falling back to screenshot mode");
+            }
+            Err(e) => {
+                error!("Failed to fetch HTML for {url}, falling back to screenshot mode: {e}");
+            }
+        }
+    }
+
     let img = fetch_screenshot(url).await?;
     *state.last_image.write().await = Some(img.clone());
     let content = fetch_llm_hallucinations(LlmInput::Image(&img)).await?;
+    state
+        .cache
+        .put(key, content.clone(), Some(img), cache::ContentKind::Url)
+        .await;
     Ok(content)
 }
 
+/// Fetches `url` and extracts its readable text, for the cheap text-first path. Returns
+/// `Ok(None)` when the document isn't HTML or turns out to have no usable text (e.g. it's
+/// JS-rendered), so the caller can fall back to the screenshot path.
+async fn fetch_html_text(url: &Url) -> Result<Option<String>, WhateverError> {
+    debug!("Will fetch HTML for URL: {url}");
+
+    // reqwest follows redirects and reassembles chunked transfer-encoded bodies by default.
+    let client = reqwest::Client::new();
+    let response = client.get(url.clone()).send().await?;
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    if !content_type.contains("text/html") {
+        debug!("URL {url} is not HTML (Content-Type: {content_type:?}), will fall back");
+        return Ok(None);
+    }
+
+    let html = response.text().await?;
+    let text = extract_readable_text(&html);
+
+    if text.trim().is_empty() {
+        debug!("Extracted no readable text from {url}, likely JS-rendered, will fall back");
+        return Ok(None);
+    }
+
+    Ok(Some(text))
+}
+
+/// Strips `<script>`/`<style>` contents and remaining markup, leaving roughly the text a
+/// reader would see, for feeding to the text LLM prompt.
+fn extract_readable_text(html: &str) -> String {
+    let without_scripts = strip_tag_contents(html, "script");
+    let without_styles = strip_tag_contents(&without_scripts, "style");
+
+    // A space at every tag boundary keeps adjacent block elements (`<h1>Title</h1><p>Body</p>`)
+    // from gluing into one word; the whitespace normalization below collapses the extras.
+    let mut text = String::with_capacity(without_styles.len());
+    let mut in_tag = false;
+    for c in without_styles.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    decode_html_entities(&text)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Removes every `<tag>...</tag>` span (tags included) from `html`, matching `tag`
+/// case-insensitively.
+fn strip_tag_contents(html: &str, tag: &str) -> String {
+    let haystack = html.to_ascii_lowercase();
+    let open = format!("<{}", tag.to_ascii_lowercase());
+    let close = format!("</{}>", tag.to_ascii_lowercase());
+
+    let mut result = String::with_capacity(html.len());
+    let mut pos = 0;
+    while let Some(start) = haystack[pos..].find(&open) {
+        let start = pos + start;
+        result.push_str(&html[pos..start]);
+        match haystack[start..].find(&close) {
+            Some(end) => pos = start + end + close.len(),
+            None => {
+                pos = html.len();
+                break;
+            }
+        }
+    }
+    result.push_str(&html[pos..]);
+    result
+}
+
+/// Decodes the handful of HTML entities that show up most often in scraped page text.
+/// Unrecognized entities are left as-is rather than dropped.
+fn decode_html_entities(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        let tail = &rest[start..];
+
+        let decoded = tail[1..]
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&tail[1..=end]).map(|c| (c, end)));
+
+        match decoded {
+            Some((c, end)) => {
+                result.push(c);
+                rest = &tail[end + 2..];
+            }
+            None => {
+                result.push('&');
+                rest = &tail[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Decodes a single named or numeric HTML entity (without its surrounding `&`/`;`).
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => return Some('&'),
+        "lt" => return Some('<'),
+        "gt" => return Some('>'),
+        "quot" => return Some('"'),
+        "apos" => return Some('\''),
+        "nbsp" => return Some('\u{00A0}'),
+        _ => {}
+    }
+
+    let digits = entity.strip_prefix('#')?;
+    let code_point = match digits.strip_prefix('x').or_else(|| digits.strip_prefix('X')) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => digits.parse().ok()?,
+    };
+    char::from_u32(code_point)
+}
+
 #[derive(Debug)]
 enum LlmInput<'a> {
     /// Image.
@@ -134,10 +373,9 @@ async fn fetch_llm_hallucinations(input: LlmInput<'_>) -> Result<String, Whateve
     let now = chrono::Utc::now().format("%Y-%m-%d");
 
     let common_prompt = format!(
-        r"Extract the information and format it in text format according to the iCal specification.
-Return nothing but that text.
+        r"Extract one or more calendar events from the input.
 If date info is missing, such as the current year, month or day, fill it in from the current date, which is {now}.
-If no wall clock time is mentioned, make it an all-day event.
+If no wall clock time is mentioned, treat it as an all-day event.
 Assume event times are in Europe/Berlin aka CEST timezone.
 Pay attention to events spanning multiple days, and recurring events.
 If only a start time is mentioned but no end time, assume one hour duration."
@@ -175,28 +413,407 @@ If only a start time is mentioned but no end time, assume one hour duration."
             tool_calls: None,
             tool_call_id: None,
         }]),
-    );
+    )
+    .tools(vec![event_extraction_tool()]);
     debug!("LLM request: {req:?}");
 
     let result = client.chat_completion(req).await?;
-    let content = result.choices[0]
-        .message
-        .content
-        .clone()
-        .ok_or("No LLM response")?;
+    let message = &result.choices[0].message;
+
+    if let Some(tool_call) = message
+        .tool_calls
+        .as_ref()
+        .into_iter()
+        .flatten()
+        .find(|call| call.function.name.as_deref() == Some(EVENT_EXTRACTION_TOOL_NAME))
+    {
+        let arguments = tool_call
+            .function
+            .arguments
+            .as_deref()
+            .ok_or("Tool call had no arguments")?;
+        debug!("Model called {EVENT_EXTRACTION_TOOL_NAME} with: {arguments}");
+
+        let extracted: ExtractedEvents = serde_json::from_str(arguments)?;
+        return build_calendar(&extracted.events);
+    }
+
+    debug!("Model did not call {EVENT_EXTRACTION_TOOL_NAME}, falling back to raw-text parsing");
+
+    let content = message.content.clone().ok_or("No LLM response")?;
 
     debug!("LLM hallucinations: {}", content.escape_debug());
 
-    // Sanity check
-    if let Err(e) = icalendar::parser::read_calendar(&content) {
-        // Log...
-        error!("Failed to parse iCal content: {e}");
-        // ... and send it out anyway. Clients might tolerate it.
+    match canonicalize_calendar(&content) {
+        Ok(canonical) => {
+            debug!("Canonicalized iCal content successfully");
+            Ok(canonical)
+        }
+        Err(e) => {
+            error!("Failed to canonicalize iCal content, sending it out as-is. Clients might tolerate it: {e}");
+            Ok(content)
+        }
+    }
+}
+
+const EVENT_EXTRACTION_TOOL_NAME: &str = "extract_events";
+
+/// Builds a string-typed, optional-or-not JSON schema property for the event extraction tool.
+fn string_property(description: &str) -> chat_completion::JSONSchemaDefine {
+    chat_completion::JSONSchemaDefine {
+        schema_type: Some(chat_completion::JSONSchemaType::String),
+        description: Some(description.to_string()),
+        enum_values: None,
+        properties: None,
+        required: None,
+        items: None,
+    }
+}
+
+/// The `extract_events` tool schema: an array of events, each with enough structure that
+/// we can build the `.ics` entirely in Rust instead of hoping the model emits valid iCal text.
+fn event_extraction_tool() -> chat_completion::Tool {
+    let event_schema = chat_completion::JSONSchemaDefine {
+        schema_type: Some(chat_completion::JSONSchemaType::Object),
+        description: Some("A single calendar event.".to_string()),
+        enum_values: None,
+        properties: Some(std::collections::HashMap::from([
+            ("summary".to_string(), Box::new(string_property("Short title of the event."))),
+            (
+                "start".to_string(),
+                Box::new(string_property(
+                    "When the event starts, as an ISO 8601 date (all-day) or date-time.",
+                )),
+            ),
+            (
+                "end".to_string(),
+                Box::new(string_property(
+                    "When the event ends, as an ISO 8601 date (all-day) or date-time, if known.",
+                )),
+            ),
+            (
+                "all_day".to_string(),
+                Box::new(chat_completion::JSONSchemaDefine {
+                    schema_type: Some(chat_completion::JSONSchemaType::Boolean),
+                    description: Some(
+                        "Whether this is an all-day event, i.e. no wall clock time was mentioned."
+                            .to_string(),
+                    ),
+                    enum_values: None,
+                    properties: None,
+                    required: None,
+                    items: None,
+                }),
+            ),
+            ("location".to_string(), Box::new(string_property("Where the event takes place, if mentioned."))),
+            (
+                "description".to_string(),
+                Box::new(string_property("Additional details about the event, if any.")),
+            ),
+            (
+                "rrule".to_string(),
+                Box::new(string_property("An iCal RRULE value if the event recurs.")),
+            ),
+        ])),
+        required: Some(vec!["summary".to_string(), "start".to_string()]),
+        items: None,
+    };
+
+    chat_completion::Tool {
+        r#type: chat_completion::ToolType::Function,
+        function: chat_completion::Function {
+            name: EVENT_EXTRACTION_TOOL_NAME.to_string(),
+            description: Some("Record the calendar event(s) found in the input.".to_string()),
+            parameters: chat_completion::FunctionParameters {
+                schema_type: chat_completion::JSONSchemaType::Object,
+                properties: Some(std::collections::HashMap::from([(
+                    "events".to_string(),
+                    Box::new(chat_completion::JSONSchemaDefine {
+                        schema_type: Some(chat_completion::JSONSchemaType::Array),
+                        description: Some("The events found in the input.".to_string()),
+                        enum_values: None,
+                        properties: None,
+                        required: None,
+                        items: Some(Box::new(event_schema)),
+                    }),
+                )])),
+                required: Some(vec!["events".to_string()]),
+            },
+        },
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct ExtractedEvents {
+    events: Vec<ExtractedEvent>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ExtractedEvent {
+    summary: String,
+    start: String,
+    end: Option<String>,
+    all_day: Option<bool>,
+    location: Option<String>,
+    description: Option<String>,
+    rrule: Option<String>,
+}
+
+/// Builds a `.ics` calendar straight from the model's structured tool-call output, so
+/// multiple events per input and recurring events are supported without hoping the model
+/// formats free text correctly.
+fn build_calendar(events: &[ExtractedEvent]) -> Result<String, WhateverError> {
+    let mut calendar = Calendar::new();
+
+    for event in events {
+        calendar.push(build_event(event)?);
+    }
+
+    Ok(calendar.done().to_string())
+}
+
+fn build_event(event: &ExtractedEvent) -> Result<Event, WhateverError> {
+    let mut builder = Event::new();
+    builder.summary(&event.summary);
+
+    if let Some(description) = &event.description {
+        builder.description(description);
+    }
+    if let Some(location) = &event.location {
+        builder.location(location);
+    }
+    if let Some(rrule) = &event.rrule {
+        builder.add_property("RRULE", rrule);
+    }
+
+    let start = if event.all_day.unwrap_or(false) {
+        None
     } else {
-        debug!("Parsed iCal content successfully");
+        match parse_date_time(&event.start) {
+            Ok(dt) => Some(dt),
+            Err(e) => {
+                debug!(
+                    "'{}' isn't a date-time ({e}), treating '{}' as an all-day event",
+                    event.start, event.summary
+                );
+                None
+            }
+        }
+    };
+
+    match start {
+        Some(start) => {
+            let start = in_berlin(start);
+            let end = match event.end.as_deref() {
+                Some(end) => match parse_date_time(end) {
+                    Ok(dt) => in_berlin(dt),
+                    // The model gave a date-only end (e.g. "2026-07-28") alongside a
+                    // date-time start; treat it as midnight of that date rather than
+                    // losing the end date entirely.
+                    Err(_) => match parse_date(end) {
+                        Ok(date) => in_berlin(icalendar::CalendarDateTime::Floating(
+                            date.and_hms_opt(0, 0, 0).expect("midnight is a valid time"),
+                        )),
+                        Err(e) => {
+                            debug!(
+                                "Could not parse event end '{end}' ({e}), falling back to start + 1h"
+                            );
+                            add_duration(start.clone(), Duration::hours(1))
+                        }
+                    },
+                },
+                None => add_duration(start.clone(), Duration::hours(1)),
+            };
+            builder.starts(start);
+            builder.ends(end);
+        }
+        None => {
+            let start_date = parse_date(&event.start)?;
+            match event.end.as_deref().and_then(|end| parse_date(end).ok()) {
+                Some(end_date) => {
+                    builder.starts(start_date);
+                    builder.ends(end_date);
+                }
+                None => {
+                    builder.all_day(start_date);
+                }
+            }
+        }
     }
 
-    Ok(content)
+    builder.uid(&stable_uid(&event.summary, &event.start));
+    builder.timestamp(chrono::Utc::now());
+
+    Ok(builder.done())
+}
+
+/// Parses an ISO 8601 date-time, falling back to a timezone-naive ("floating") datetime if
+/// the model omitted an offset.
+fn parse_date_time(value: &str) -> Result<icalendar::CalendarDateTime, WhateverError> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(icalendar::CalendarDateTime::Utc(dt.with_timezone(&chrono::Utc)));
+    }
+
+    let naive = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .map_err(|e| format!("Could not parse '{value}' as a date-time: {e}"))?;
+    Ok(icalendar::CalendarDateTime::Floating(naive))
+}
+
+fn parse_date(value: &str) -> Result<chrono::NaiveDate, WhateverError> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|e| format!("Could not parse '{value}' as a date: {e}").into())
+}
+
+/// Adds `dur` to a `CalendarDateTime`, preserving whichever timezone variant it was in.
+fn add_duration(dt: icalendar::CalendarDateTime, dur: Duration) -> icalendar::CalendarDateTime {
+    match dt {
+        icalendar::CalendarDateTime::Floating(naive) => icalendar::CalendarDateTime::Floating(naive + dur),
+        icalendar::CalendarDateTime::Utc(utc) => icalendar::CalendarDateTime::Utc(utc + dur),
+        icalendar::CalendarDateTime::WithTimezone { date_time, tzid } => {
+            icalendar::CalendarDateTime::WithTimezone { date_time: date_time + dur, tzid }
+        }
+    }
+}
+
+/// Reparses `content` as an iCal calendar and rebuilds it via [`icalendar`]'s builder types,
+/// enforcing invariants the LLM routinely breaks (missing `UID`/`DTSTAMP`, missing `DTEND`,
+/// ambiguous timezone) so clients always receive schema-valid ICS.
+fn canonicalize_calendar(content: &str) -> Result<String, WhateverError> {
+    let parsed: Calendar = content.parse().map_err(|e| {
+        format!(
+            "Failed to parse iCal content: {e} (fields found in raw content: {})",
+            describe_present_fields(content)
+        )
+    })?;
+
+    let mut canonical = Calendar::new();
+
+    for component in &parsed.components {
+        let CalendarComponent::Event(event) = component else {
+            debug!("Skipping non-VEVENT component while canonicalizing: {component:?}");
+            continue;
+        };
+
+        canonical.push(canonicalize_event(event));
+    }
+
+    Ok(canonical.done().to_string())
+}
+
+/// Rebuilds a single `VEVENT` with stable identifiers and normalized start/end/timezone fields.
+fn canonicalize_event(event: &Event) -> Event {
+    let mut canonical = Event::new();
+
+    if let Some(summary) = event.get_summary() {
+        canonical.summary(summary);
+    }
+    if let Some(description) = event.get_description() {
+        canonical.description(description);
+    }
+    if let Some(rrule) = event.property_value("RRULE") {
+        canonical.add_property("RRULE", rrule);
+    }
+
+    let summary = event.get_summary().unwrap_or_default();
+    let dtstart = event.get_start();
+
+    match dtstart {
+        Some(icalendar::DatePerhapsTime::Date(date)) => {
+            canonical.all_day(date);
+        }
+        Some(icalendar::DatePerhapsTime::DateTime(start)) => {
+            let start = in_berlin(start);
+
+            match event.get_end() {
+                Some(icalendar::DatePerhapsTime::DateTime(end)) => {
+                    canonical.ends(in_berlin(end));
+                }
+                Some(icalendar::DatePerhapsTime::Date(end)) => {
+                    canonical.ends(end);
+                }
+                None => {
+                    canonical.ends(add_duration(start.clone(), Duration::hours(1)));
+                }
+            }
+
+            canonical.starts(start);
+        }
+        None => {
+            error!("Event '{summary}' has no DTSTART, leaving it unset");
+        }
+    }
+
+    let uid = event
+        .get_uid()
+        .map(str::to_string)
+        .unwrap_or_else(|| stable_uid(summary, &dtstart_key(dtstart.as_ref())));
+    canonical.uid(&uid);
+
+    match event.get_timestamp() {
+        Some(dtstamp) => {
+            canonical.timestamp(dtstamp);
+        }
+        None => {
+            canonical.timestamp(chrono::Utc::now());
+        }
+    }
+
+    canonical.done()
+}
+
+/// Reads a floating (timezone-naive) `CalendarDateTime` as `Europe/Berlin` wall-clock time,
+/// per the prompt's assumption, and converts it to UTC. Values that already carry a
+/// timezone or are UTC are returned unchanged.
+fn in_berlin(dt: icalendar::CalendarDateTime) -> icalendar::CalendarDateTime {
+    match dt {
+        // Tagging this as `TZID=Europe/Berlin` without emitting a matching `VTIMEZONE`
+        // component would produce ICS that strict clients reject (RFC 5545 ยง3.2.19).
+        // Converting to UTC instead sidesteps that while still anchoring the wall-clock
+        // time assumed by the prompt to the correct offset, DST included.
+        icalendar::CalendarDateTime::Floating(naive) => {
+            let berlin = chrono_tz::Europe::Berlin
+                .from_local_datetime(&naive)
+                .single()
+                .or_else(|| chrono_tz::Europe::Berlin.from_local_datetime(&naive).earliest());
+
+            let utc = match berlin {
+                Some(dt) => dt.with_timezone(&chrono::Utc),
+                None => chrono::DateTime::from_naive_utc_and_offset(naive, chrono::Utc),
+            };
+
+            icalendar::CalendarDateTime::Utc(utc)
+        }
+        already_zoned => already_zoned,
+    }
+}
+
+fn dtstart_key(dtstart: Option<&icalendar::DatePerhapsTime>) -> String {
+    dtstart.map(|d| format!("{d:?}")).unwrap_or_default()
+}
+
+/// Best-effort rundown of which fields we care about (`DTSTART`, `DTEND`, `SUMMARY`,
+/// `DESCRIPTION`, `RRULE`, `UID`) show up in `content` at all, for logging when the content
+/// fails to parse as iCal in the first place and we can't point at a specific bad value.
+fn describe_present_fields(content: &str) -> String {
+    const FIELDS: [&str; 6] = ["DTSTART", "DTEND", "SUMMARY", "DESCRIPTION", "RRULE", "UID"];
+
+    FIELDS
+        .iter()
+        .map(|field| {
+            let present = content.lines().any(|line| line.starts_with(field));
+            format!("{field}={present}")
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Derives a stable `UID` from the event's summary and start time so re-parsing the same
+/// event doesn't mint a new identity every time.
+fn stable_uid(summary: &str, dtstart: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    summary.hash(&mut hasher);
+    dtstart.hash(&mut hasher);
+    format!("{:x}@ai-calamba", hasher.finish())
 }
 
 async fn fetch_screenshot(url: &Url) -> Result<Vec<u8>, WhateverError> {
@@ -246,10 +863,91 @@ async fn serve_last_image(State(state): State<Arc<AppState>>) -> impl IntoRespon
     }
 }
 
+/// An ICS response with the headers clients (browsers, mail clients) need to recognize and
+/// download it as a calendar rather than plain text.
+struct Ics(String);
+
+impl IntoResponse for Ics {
+    fn into_response(self) -> axum::response::Response {
+        (
+            [
+                (axum::http::header::CONTENT_TYPE, "text/calendar; charset=utf-8"),
+                (
+                    axum::http::header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"event.ics\"",
+                ),
+            ],
+            self.0,
+        )
+            .into_response()
+    }
+}
+
+/// Parses `ics` and upserts its `VEVENT`s (keyed by `UID`) into the aggregated feed served
+/// at `/calendar.ics`, so subscribers see newly submitted events without downloading each
+/// file by hand. Keying by `UID` means resubmitting the same input repeatedly (e.g. a cache
+/// hit) replaces its entry instead of growing the feed with duplicate-UID components.
+async fn record_events(state: &AppState, ics: &str) {
+    match ics.parse::<Calendar>() {
+        Ok(calendar) => {
+            let mut events = state.events.write().await;
+            for component in calendar.components {
+                if let CalendarComponent::Event(event) = component {
+                    match event.get_uid() {
+                        Some(uid) => {
+                            events.insert(uid.to_string(), event);
+                        }
+                        None => {
+                            error!("Generated VEVENT has no UID, dropping it from the aggregated feed")
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => error!("Failed to parse generated ICS for the aggregated feed: {e}"),
+    }
+}
+
+#[derive(Deserialize)]
+struct FeedQuery {
+    token: Option<String>,
+}
+
+/// Serves the aggregated `VCALENDAR` of every event generated so far, for `webcal`-style
+/// subscriptions. Deliberately left outside the bearer-token-protected router since
+/// calendar clients can't send custom headers; if `CALENDAR_FEED_TOKEN` is set, a matching
+/// `?token=` query parameter is required instead. **If it's unset, this endpoint is
+/// unauthenticated and exposes every event ever generated (via paid calls) to anyone with
+/// the URL** — set `CALENDAR_FEED_TOKEN` before deploying this beyond localhost.
+async fn serve_calendar_feed(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<FeedQuery>,
+) -> impl IntoResponse {
+    if let Ok(expected) = env::var("CALENDAR_FEED_TOKEN") {
+        let provided = query.token.unwrap_or_default();
+        if !auth::constant_time_eq(provided.as_bytes(), expected.as_bytes()) {
+            return axum::http::StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+
+    let mut calendar = Calendar::new();
+    for event in state.events.read().await.values() {
+        calendar.push(event.clone());
+    }
+
+    Ics(calendar.done().to_string()).into_response()
+}
+
 struct AppState {
     last_image: RwLock<Option<Vec<u8>>>,
+    cache: cache::Cache,
+    events: RwLock<std::collections::HashMap<String, Event>>,
 }
 
+/// Default cache settings, overridable via `CACHE_TTL_SECONDS` and `CACHE_MAX_ENTRIES`.
+const DEFAULT_CACHE_TTL_SECONDS: u64 = 60 * 60 * 24;
+const DEFAULT_CACHE_MAX_ENTRIES: usize = 1000;
+
 #[tokio::main]
 async fn main() {
     env_logger::builder()
@@ -258,15 +956,40 @@ async fn main() {
 
     info!("Starting server");
 
+    if env::var("CALENDAR_FEED_TOKEN").is_err() {
+        log::warn!(
+            "CALENDAR_FEED_TOKEN is not set: /calendar.ics is unauthenticated and exposes every \
+             generated event to anyone with the URL"
+        );
+    }
+
+    let cache_ttl = env::var("CACHE_TTL_SECONDS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_CACHE_TTL_SECONDS);
+    let cache_max_entries = env::var("CACHE_MAX_ENTRIES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_CACHE_MAX_ENTRIES);
+
     let state = Arc::new(AppState {
         last_image: RwLock::new(None),
+        cache: cache::Cache::new(std::time::Duration::from_secs(cache_ttl), cache_max_entries),
+        events: RwLock::new(std::collections::HashMap::new()),
     });
 
-    let app = Router::new()
-        .route("/", get(index))
+    // `/text` and `/image` hit billable OpenAI/APIFlash calls and `/image/last` serves a
+    // screenshot one of them paid for, so they sit behind bearer-token auth.
+    let protected = Router::new()
         .route("/text", post(handle_text))
         .route("/image", post(handle_image))
         .route("/image/last", get(serve_last_image)) // For debugging
+        .route_layer(axum::middleware::from_fn(auth::require_bearer_token));
+
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/calendar.ics", get(serve_calendar_feed))
+        .merge(protected)
         // Need to handle images larger than 2 MB (axum default)
         .layer(DefaultBodyLimit::max(10_000_000))
         .with_state(state);